@@ -0,0 +1,151 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::data::Topic;
+
+// A trimmed version of RAKE's bundled English stoplist (Rose et al., "Automatic Keyword
+// Extraction from Individual Documents"), plus a small Rust-ecosystem stoplist so words that
+// show up in almost every crate description don't dominate every extracted phrase.
+const STOPWORDS: &[&str] = &[
+    "a", "about", "above", "after", "again", "against", "all", "am", "an", "and", "any", "are",
+    "aren't", "as", "at", "be", "because", "been", "before", "being", "below", "between", "both",
+    "but", "by", "can't", "cannot", "could", "couldn't", "did", "didn't", "do", "does", "doesn't",
+    "doing", "don't", "down", "during", "each", "few", "for", "from", "further", "had", "hadn't",
+    "has", "hasn't", "have", "haven't", "having", "he", "her", "here", "hers", "herself", "him",
+    "himself", "his", "how", "i", "if", "in", "into", "is", "isn't", "it", "it's", "its", "itself",
+    "let's", "me", "more", "most", "mustn't", "my", "myself", "no", "nor", "not", "of", "off",
+    "on", "once", "only", "or", "other", "ought", "our", "ours", "ourselves", "out", "over", "own",
+    "same", "shan't", "she", "should", "shouldn't", "so", "some", "such", "than", "that", "the",
+    "their", "theirs", "them", "themselves", "then", "there", "these", "they", "this", "those",
+    "through", "to", "too", "under", "until", "up", "very", "was", "wasn't", "we", "were",
+    "weren't", "what", "when", "where", "which", "while", "who", "whom", "why", "with", "won't",
+    "would", "wouldn't", "you", "your", "yours", "yourself", "yourselves",
+    // Rust-ecosystem noise: generic enough to appear in almost any crate's description without
+    // telling us anything about what the crate actually does.
+    "rust", "crate", "crates", "library", "implementation",
+];
+
+fn is_stopword(word: &str) -> bool {
+    STOPWORDS.contains(&word)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+// Splits `text` into candidate phrases by cutting on stopwords and punctuation, the first step of
+// RAKE: what's left between two stopwords (or a stopword and the text boundary) is a candidate
+// keyword phrase.
+fn candidate_phrases(text: &str) -> Vec<Vec<String>> {
+    let mut phrases = Vec::new();
+    let mut current = Vec::new();
+
+    for token in text.split(|c: char| !is_word_char(c)) {
+        if token.is_empty() {
+            continue;
+        }
+
+        let word = token.to_lowercase();
+        if is_stopword(&word) {
+            if !current.is_empty() {
+                phrases.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(word);
+        }
+    }
+
+    if !current.is_empty() {
+        phrases.push(current);
+    }
+
+    phrases
+}
+
+// Scores each word as `degree(word) / frequency(word)`, where degree is the summed length of
+// every phrase the word co-occurs in (including itself) and frequency is how many times it
+// occurs across all phrases. Words that mostly show up inside long phrases score higher than ones that tend to
+// stand alone.
+fn word_scores(phrases: &[Vec<String>]) -> HashMap<String, f32> {
+    let mut degree: HashMap<String, u32> = HashMap::new();
+    let mut frequency: HashMap<String, u32> = HashMap::new();
+
+    for phrase in phrases {
+        let len = phrase.len() as u32;
+        for word in phrase {
+            *degree.entry(word.clone()).or_insert(0) += len;
+            *frequency.entry(word.clone()).or_insert(0) += 1;
+        }
+    }
+
+    degree
+        .into_iter()
+        .map(|(word, degree)| {
+            let score = degree as f32 / frequency[&word] as f32;
+            (word, score)
+        })
+        .collect()
+}
+
+/// Extracts up to `limit` candidate keyword phrases from `text`, highest-scoring first, using the
+/// RAKE algorithm: split on stopwords/punctuation, score words by `degree/frequency`, then score
+/// each phrase as the sum of its member word scores.
+pub fn extract_keywords(text: &str, limit: usize) -> Vec<String> {
+    let phrases = candidate_phrases(text);
+    let scores = word_scores(&phrases);
+
+    // Fold by phrase text *before* sorting, so two occurrences of the same candidate phrase (a
+    // repeated word in the description, say) collapse into one entry instead of relying on
+    // `dedup_by`, which only catches duplicates that end up adjacent after the sort - and ties
+    // are the common case here, since identical phrases always score identically.
+    let mut phrase_scores: HashMap<String, f32> = HashMap::new();
+    for phrase in phrases {
+        let score: f32 = phrase.iter().map(|word| scores[word]).sum();
+        phrase_scores
+            .entry(phrase.join(" "))
+            .and_modify(|existing| *existing = existing.max(score))
+            .or_insert(score);
+    }
+
+    let mut scored_phrases: Vec<(String, f32)> = phrase_scores.into_iter().collect();
+    scored_phrases.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    scored_phrases
+        .into_iter()
+        .take(limit)
+        .map(|(phrase, _)| phrase)
+        .collect()
+}
+
+/// A small, hand-maintained keyword -> topic table. Grow this alongside `Topic` as new topics are
+/// added; phrases that don't match anything are just dropped.
+pub fn keyword_topic_table() -> HashMap<&'static str, Topic> {
+    let mut table = HashMap::new();
+
+    for keyword in ["chat", "messaging", "email", "protocol", "networking", "irc", "websocket"] {
+        table.insert(keyword, Topic::Communication);
+    }
+    for keyword in ["drone", "uav", "quadcopter", "flight", "autopilot", "mavlink"] {
+        table.insert(keyword, Topic::Drones);
+    }
+
+    table
+}
+
+/// Maps extracted phrases to topics via `table`, matching on individual words within each phrase
+/// so multi-word phrases still hit single-word table entries.
+pub fn suggest_topics(phrases: &[String], table: &HashMap<&'static str, Topic>) -> Vec<Topic> {
+    let mut seen = HashSet::new();
+    let mut topics = Vec::new();
+
+    for phrase in phrases {
+        for word in phrase.split(' ') {
+            if let Some(topic) = table.get(word) {
+                if seen.insert(*topic) {
+                    topics.push(*topic);
+                }
+            }
+        }
+    }
+
+    topics
+}
@@ -3,18 +3,46 @@ use std::fmt::Display;
 use anyhow::Result as AnyResult;
 use chrono::{DateTime, Utc};
 use crates_io_api::Crate;
+use semver::Version;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::{crates::CratesIo, github::RepoData};
+use crate::{classify, crates::CratesIo, github::RepoData};
 
-#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[serde(rename_all = "kebab-case")]
 pub enum Topic {
     Communication,
     Drones,
 }
 
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MaintenanceStatus {
+    ActivelyDeveloped,
+    PassivelyMaintained,
+    LookingForMaintainer,
+    Deprecated,
+    Unmaintained,
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CrateOwnerKind {
+    User,
+    Team,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CrateOwner {
+    pub login: String,
+    pub display_name: Option<String>,
+    pub github_id: Option<i64>,
+    pub kind: CrateOwnerKind,
+    pub avatar_url: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(tag = "kind")]
 pub enum InputCrateInfo {
@@ -84,6 +112,23 @@ pub struct GeneratedCrateInfo {
 
     #[serde(rename = "repo", skip_serializing_if = "Option::is_none")]
     pub repo: Option<RepoData>,
+
+    // Distinct crates depending on this one (normal + optional/dev), sourced from the db-dump's
+    // `dependencies` table. `None` when the db-dump wasn't loaded or the crate wasn't found in it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reverse_dependency_count: Option<u32>,
+
+    pub maintenance_status: MaintenanceStatus,
+
+    // Populated from the db-dump's `crate_owners`/`users`/`teams` tables; empty when the db-dump
+    // wasn't loaded or the crate has no recorded owners there.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<CrateOwner>,
+
+    // Topics auto-classified from the description/keywords/categories when `topics` wasn't set
+    // explicitly in the input. A human should confirm these before they become authoritative.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suggested_topics: Vec<Topic>,
 }
 
 impl GeneratedCrateInfo {
@@ -100,6 +145,10 @@ impl GeneratedCrateInfo {
             score: None,
             repo: krate.repo,
             krate: krate.krate,
+            reverse_dependency_count: None,
+            maintenance_status: MaintenanceStatus::Unknown,
+            owners: Vec::new(),
+            suggested_topics: Vec::new(),
         }
     }
 
@@ -109,6 +158,10 @@ impl GeneratedCrateInfo {
             score: None,
             krate: None,
             repo: None,
+            reverse_dependency_count: None,
+            maintenance_status: MaintenanceStatus::Unknown,
+            owners: Vec::new(),
+            suggested_topics: Vec::new(),
         };
 
         if let Some(crate_name) = krate.name {
@@ -139,6 +192,13 @@ impl GeneratedCrateInfo {
     }
 }
 
+// Relative weights of the sub-scores blended together in `update_score`. They must sum to 1.0
+// so the final score stays on a 0-100 scale.
+const DOWNLOADS_WEIGHT: f32 = 0.4;
+const CADENCE_WEIGHT: f32 = 0.2;
+const ACTIVITY_WEIGHT: f32 = 0.2;
+const REVERSE_DEPENDENCY_WEIGHT: f32 = 0.2;
+
 impl GeneratedCrateInfo {
     //   In calculating last_activity, we only scrape last_commit for github-based crates
     //   so this is unfair to projects that host source elsewhere.
@@ -155,20 +215,78 @@ impl GeneratedCrateInfo {
         last_activity
     }
 
-    pub fn update_score(&mut self) {
-        if self.krate.is_none() {
-            // crate is not published to crates.io
-            self.score = Some(0);
+    // The crates.io API still serves the deprecated `[badges.maintenance]` manifest badge, which
+    // is the most direct signal a maintainer can give about a crate's status.
+    fn badge_maintenance_status(&self) -> Option<MaintenanceStatus> {
+        let krate = self.krate.as_ref()?;
+        let badge = krate.badges.iter().find(|b| b.badge_type == "maintenance")?;
+        let status = badge.attributes.get("status")?.as_ref()?;
+
+        match status.as_str() {
+            "actively-developed" => Some(MaintenanceStatus::ActivelyDeveloped),
+            "passively-maintained" | "as-is" => Some(MaintenanceStatus::PassivelyMaintained),
+            "looking-for-maintainer" => Some(MaintenanceStatus::LookingForMaintainer),
+            "deprecated" => Some(MaintenanceStatus::Deprecated),
+            // "experimental" and "none" don't map to any of our buckets well enough to trust
+            // over the heuristics below.
+            _ => None,
+        }
+    }
+
+    // Falls back to inferring a maintenance status from activity and open-issue signals when the
+    // crate doesn't carry an explicit `[badges.maintenance]` entry.
+    fn derive_maintenance_status(&self) -> MaintenanceStatus {
+        if let Some(status) = self.badge_maintenance_status() {
+            return status;
+        }
+
+        let krate = match self.krate.as_ref() {
+            Some(krate) => krate,
+            None => return MaintenanceStatus::Unknown,
+        };
+
+        // A crate with no installable versions left (the summary we have doesn't expose
+        // per-version yanked flags, but an empty version list is the closest proxy available)
+        // can't realistically still be in active use.
+        if matches!(&krate.versions, Some(versions) if versions.is_empty()) {
+            return MaintenanceStatus::Deprecated;
         }
 
-        let coefficient = match self.last_activity() {
+        let inactive_days = self
+            .last_activity()
+            .map(|last_activity| (Utc::now() - last_activity).num_days());
+        let open_issues = self
+            .repo
+            .as_ref()
+            .and_then(|r| r.open_issues_count)
+            .unwrap_or(0);
+
+        match inactive_days {
+            None => MaintenanceStatus::Unknown,
+            Some(days) if days <= 180 => MaintenanceStatus::ActivelyDeveloped,
+            Some(days) if days <= 365 => MaintenanceStatus::PassivelyMaintained,
+            // Long-inactive crates with a lot of open issues are more likely abandoned than
+            // merely stable, so they're flagged as looking for a new maintainer.
+            Some(days) if days <= 730 => {
+                if open_issues > 50 {
+                    MaintenanceStatus::LookingForMaintainer
+                } else {
+                    MaintenanceStatus::PassivelyMaintained
+                }
+            }
+            Some(_) => MaintenanceStatus::Unmaintained,
+        }
+    }
+
+    // This is really simple, but basically calls any crate with activity in 6 months as maintained
+    // trying to recognize that some crates may actually be stable enough to require infrequent changes
+    // From 6-12 months, it's maintenance state is less certain, and after a year without activity, it's likely unmaintained
+    fn activity_score(&self) -> f32 {
+        match self.last_activity() {
             None => 0.1,
             Some(last_activity) => {
                 let inactive_days = (Utc::now() - last_activity).num_days();
 
-                // This is really simple, but basically calls any crate with activity in 6 months as maintained
-                // trying to recognize that some crates may actually be stable enough to require infrequent changes
-                // From 6-12 months, it's maintenance state is less certain, and after a year without activity, it's likely unmaintained
                 if inactive_days <= 180 {
                     1.0
                 } else if inactive_days <= 365 {
@@ -177,13 +295,123 @@ impl GeneratedCrateInfo {
                     0.1
                 }
             }
-        };
+        }
+    }
 
+    // Scales recent downloads against the busiest crate in the generated set, so a stable but
+    // low-traffic crate isn't drowned out by the absolute download counts of the most popular one.
+    fn downloads_score(&self, max_recent_downloads: u64) -> f32 {
         let recent_downloads = self
             .krate
             .as_ref()
             .and_then(|k| k.recent_downloads)
             .unwrap_or(0);
-        self.score = Some(f32::floor(coefficient * recent_downloads as f32) as u64);
+
+        if max_recent_downloads == 0 {
+            return 0.0;
+        }
+
+        f32::log10(recent_downloads as f32 + 1.0) / f32::log10(max_recent_downloads as f32 + 1.0)
+    }
+
+    // Rewards crates that have graduated to a stable 1.0.0+ release, and penalizes ones whose only
+    // versions are still pre-1.0 or yanked, since those are more likely to carry breaking changes
+    // or go unmaintained. `Crate.versions` only carries version ids, not per-version yanked flags,
+    // so an empty version list (no installable version left) is used as the closest available
+    // proxy for "yanked" - the same proxy `derive_maintenance_status` uses.
+    fn release_cadence_score(&self) -> f32 {
+        let krate = match self.krate.as_ref() {
+            Some(krate) => krate,
+            None => return 0.0,
+        };
+
+        let max_version = match Version::parse(&krate.max_version) {
+            Ok(version) => version,
+            Err(_) => return 0.2,
+        };
+
+        if max_version.major >= 1 {
+            1.0
+        } else {
+            match krate.versions.as_ref().map(|versions| versions.len()) {
+                // No installable version left at all - our best proxy for "every release has been
+                // yanked" - is worse than merely being a lone early/abandoned pre-1.0 experiment.
+                Some(0) => 0.0,
+                Some(1) | None => 0.2,
+                // Multiple pre-1.0 releases at least show the crate is iterating.
+                _ => 0.5,
+            }
+        }
+    }
+
+    // Scales reverse-dependency counts the same way downloads are scaled: relative to the crate
+    // with the most dependents in the generated set, so the signal stays meaningful regardless
+    // of how large the corpus is. Crates the db-dump has no data for score 0.0, the same as a
+    // crate that nothing depends on.
+    fn reverse_dependency_score(&self, max_reverse_dependency_count: u32) -> f32 {
+        let count = match self.reverse_dependency_count {
+            Some(count) => count,
+            None => return 0.0,
+        };
+
+        if max_reverse_dependency_count == 0 {
+            return 0.0;
+        }
+
+        f32::log10(count as f32 + 1.0) / f32::log10(max_reverse_dependency_count as f32 + 1.0)
+    }
+
+    pub fn update_score(&mut self, max_recent_downloads: u64, max_reverse_dependency_count: u32) {
+        self.maintenance_status = self.derive_maintenance_status();
+
+        if self.krate.is_none() {
+            // crate is not published to crates.io
+            self.score = Some(0);
+            return;
+        }
+
+        let blended = DOWNLOADS_WEIGHT * self.downloads_score(max_recent_downloads)
+            + CADENCE_WEIGHT * self.release_cadence_score()
+            + ACTIVITY_WEIGHT * self.activity_score()
+            + REVERSE_DEPENDENCY_WEIGHT
+                * self.reverse_dependency_score(max_reverse_dependency_count);
+
+        // A popular-but-abandoned crate shouldn't rank alongside ones that are actually cared for.
+        let score = match self.maintenance_status {
+            MaintenanceStatus::Deprecated => 0.0,
+            MaintenanceStatus::Unmaintained => blended * 100.0 * 0.2,
+            _ => blended * 100.0,
+        };
+
+        self.score = Some(f32::floor(score) as u64);
+    }
+
+    /// Suggests topics via keyword extraction when none were set explicitly in the input. Leaves
+    /// `topics` itself untouched - these are surfaced separately so a human can confirm them.
+    pub fn update_suggested_topics(&mut self) {
+        if !self.topics.is_empty() {
+            return;
+        }
+
+        let krate = match self.krate.as_ref() {
+            Some(krate) => krate,
+            None => return,
+        };
+
+        let mut text = String::new();
+        if let Some(description) = &krate.description {
+            text.push_str(description);
+            text.push(' ');
+        }
+        if let Some(keywords) = &krate.keywords {
+            text.push_str(&keywords.join(" "));
+            text.push(' ');
+        }
+        if let Some(categories) = &krate.categories {
+            text.push_str(&categories.join(" "));
+        }
+
+        let phrases = classify::extract_keywords(&text, 10);
+        self.suggested_topics = classify::suggest_topics(&phrases, &classify::keyword_topic_table());
     }
 }
@@ -1,4 +1,4 @@
-use crate::util::{cache_path, read_cache, write_cache};
+use crate::util::{cache_path, read_cache, write_cache, DEFAULT_CACHE_TTL};
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
 use octocrab::Octocrab;
@@ -6,6 +6,10 @@ use serde::{Deserialize, Serialize};
 use serde_json::{from_value, Value};
 use std::env;
 
+// Bump whenever `RepoData`'s shape changes, so cache entries written by an older version of the
+// scraper are refetched instead of failing to deserialize (or silently misparsing).
+const REPO_DATA_CACHE_SCHEMA_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepoData {
     pub name: String,
@@ -85,19 +89,19 @@ impl Github {
         }
     }
 
-    // TODO: use cache where available
     pub async fn get_repo_data(&self, username: &str, repo: &str) -> Result<RepoData> {
         let cache_path = cache_path("github", &format!("{}--{}", username, repo))?;
 
-        let data = match read_cache(&cache_path) {
-            Ok(data) => data,
-            Err(_) => {
-                let data = self.fetch_remote_repo_data(username, repo).await?;
-                let _ = write_cache(&cache_path, &data);
-                data
-            }
-        };
+        if let Ok(data) =
+            read_cache::<RepoData>(&cache_path, REPO_DATA_CACHE_SCHEMA_VERSION, DEFAULT_CACHE_TTL)
+        {
+            return Ok(data);
+        }
+
+        let value = self.fetch_remote_repo_data(username, repo).await?;
+        let data = RepoData::from_graphql_data(&format!("{}/{}", username, repo), &value)?;
+        let _ = write_cache(&cache_path, REPO_DATA_CACHE_SCHEMA_VERSION, &data);
 
-        RepoData::from_graphql_data(&format!("{}/{}", username, repo), &data)
+        Ok(data)
     }
 }
@@ -1,5 +1,6 @@
 use anyhow::{bail, ensure, Context, Result as AnyResult};
 
+mod classify;
 mod crates;
 mod data;
 mod github;
@@ -8,10 +9,10 @@ mod util;
 use chrono::{TimeZone, Utc};
 use chrono_tz::Europe::London;
 use crates_io_api::CrateLinks;
-use data::{GeneratedCrateInfo, InputCrateInfo};
+use data::{CrateOwner, CrateOwnerKind, GeneratedCrateInfo, InputCrateInfo};
 use github::Github;
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeSet, HashMap, HashSet},
     env,
     fs::File,
     io::Write,
@@ -23,6 +24,20 @@ use crate::data::ManualCrateInfo;
 
 static DB_DUMP: &str = "db-dump.tar.gz";
 
+// Mirrors crates.rs's `RevDepCount`: how many distinct crates depend on a given crate, split
+// between "real" (normal, non-dev) dependencies and merely optional/dev ones.
+#[derive(Default, Clone, Copy)]
+struct RevDepCount {
+    def: u32,
+    opt: u32,
+}
+
+impl RevDepCount {
+    fn total(&self) -> u32 {
+        self.def + self.opt
+    }
+}
+
 #[tokio::main]
 async fn main() -> AnyResult<()> {
     let mut args = env::args();
@@ -81,7 +96,6 @@ async fn main() -> AnyResult<()> {
                 }
             }
         }
-        gen.update_score();
         generated.push(gen);
     }
 
@@ -95,6 +109,25 @@ async fn main() -> AnyResult<()> {
         db_dump::crates::CrateId,
         Vec<db_dump::categories::CategoryId>,
     > = HashMap::new();
+    // crate_id of the dependent crate, keyed by the version that declares the dependency, so
+    // `dependencies` rows (which only know the *target* crate_id) can be resolved back to who's
+    // depending on whom.
+    let mut version_crate: HashMap<db_dump::versions::VersionId, db_dump::crates::CrateId> =
+        HashMap::new();
+    // Per dependency target crate, the ids of the crates depending on it, split into "default" and
+    // "optional or dev" so each can be weighted separately later; keyed and built up directly while
+    // streaming `dependencies` rather than buffering the rows, since it's one of the dump's
+    // largest tables.
+    type DependentSets = (HashSet<db_dump::crates::CrateId>, HashSet<db_dump::crates::CrateId>);
+    let mut reverse_deps: HashMap<db_dump::crates::CrateId, DependentSets> = HashMap::new();
+    let mut users_by_id: HashMap<db_dump::users::UserId, db_dump::users::Row> = HashMap::new();
+    let mut teams_by_id: HashMap<db_dump::teams::TeamId, db_dump::teams::Row> = HashMap::new();
+    let mut crate_owner_rows: Vec<db_dump::crate_owners::Row> = Vec::new();
+    // All version ids and their semver `num`, per crate, so the db-dump-discovered crates can
+    // carry the same `versions`/`max_version` data as the crates.io-API-discovered ones.
+    let mut versions_by_crate: HashMap<db_dump::crates::CrateId, Vec<u64>> = HashMap::new();
+    let mut version_num: HashMap<db_dump::versions::VersionId, String> = HashMap::new();
+
     if !requested_categories.is_empty() {
         make_sure_db_is_available().await?;
 
@@ -119,8 +152,75 @@ async fn main() -> AnyResult<()> {
                     .and_modify(|categories| categories.push(row.category_id))
                     .or_insert(vec![row.category_id]);
             })
+            .versions(|row| {
+                version_crate.insert(row.id, row.crate_id);
+                versions_by_crate
+                    .entry(row.crate_id)
+                    .or_default()
+                    .push(row.id.0);
+                version_num.insert(row.id, row.num.clone());
+            })
+            .users(|row| {
+                users_by_id.insert(row.id, row);
+            })
+            .teams(|row| {
+                teams_by_id.insert(row.id, row);
+            })
+            .crate_owners(|row| {
+                crate_owner_rows.push(row);
+            })
             .load("./db-dump.tar.gz")?;
 
+        // `version_downloads` and `dependencies` are two of the dump's largest tables, so rather
+        // than buffer either, run a second pass over the same tar now that `version_crate` is
+        // guaranteed complete and aggregate rows directly into `recent_downloads_by_crate` and
+        // `reverse_deps` as they stream by.
+        let ninety_days_ago = Utc::now().date_naive() - chrono::Duration::days(90);
+        let mut recent_downloads_by_crate: HashMap<db_dump::crates::CrateId, u64> = HashMap::new();
+        db_dump::Loader::new()
+            .version_downloads(|row| {
+                if row.date < ninety_days_ago {
+                    return;
+                }
+
+                if let Some(crate_id) = version_crate.get(&row.version_id) {
+                    *recent_downloads_by_crate.entry(*crate_id).or_insert(0) += row.downloads as u64;
+                }
+            })
+            .dependencies(|row| {
+                let dependent_crate_id = match version_crate.get(&row.version_id) {
+                    Some(id) => *id,
+                    None => return,
+                };
+
+                // Cargo rejects `optional = true` on `[dev-dependencies]`, so `optional` alone
+                // would silently file every dev-dependency under "real, non-dev" deps - check the
+                // dependency kind too.
+                let is_optional_or_dev =
+                    row.optional || matches!(row.kind, db_dump::dependencies::DependencyKind::Dev);
+
+                let (def, opt) = reverse_deps.entry(row.crate_id).or_default();
+                if is_optional_or_dev {
+                    opt.insert(dependent_crate_id);
+                } else {
+                    def.insert(dependent_crate_id);
+                }
+            })
+            .load("./db-dump.tar.gz")?;
+
+        let max_version_by_crate: HashMap<db_dump::crates::CrateId, String> = versions_by_crate
+            .iter()
+            .filter_map(|(crate_id, version_ids)| {
+                let max_version = version_ids
+                    .iter()
+                    .filter_map(|id| version_num.get(&db_dump::versions::VersionId(*id)))
+                    .filter_map(|num| semver::Version::parse(num).ok())
+                    .max()?;
+
+                Some((*crate_id, max_version.to_string()))
+            })
+            .collect();
+
         let relevant_categories = all_categories
             .iter()
             .filter(|c| requested_categories.contains(&c.category))
@@ -174,12 +274,14 @@ async fn main() -> AnyResult<()> {
                         homepage: krate.homepage.clone(),
                         repository: krate.repository.clone(),
                         downloads: krate.downloads,
-                        recent_downloads: None,
+                        recent_downloads: recent_downloads_by_crate.get(&krate.id).copied(),
                         categories: Some(current_crate_categories),
                         keywords: None,
-                        // TODO: versions can be found while iterating on the db
-                        versions: None,
-                        max_version: "unknown".to_string(),
+                        versions: versions_by_crate.get(&krate.id).cloned(),
+                        max_version: max_version_by_crate
+                            .get(&krate.id)
+                            .cloned()
+                            .unwrap_or_else(|| "unknown".to_string()),
                         links: CrateLinks {
                             owner_team: "unknown".to_string(),
                             owner_user: "unknown".to_string(),
@@ -202,13 +304,104 @@ async fn main() -> AnyResult<()> {
                     repo: None,
                 });
 
-                let mut gen = GeneratedCrateInfo::try_from(info).await?;
-                gen.update_score();
+                let gen = GeneratedCrateInfo::try_from(info).await?;
                 generated.push(gen);
             }
         }
     }
 
+    // `reverse_deps` was already built directly from the streamed `dependencies` rows above; dedupe
+    // each target crate's dependent sets down to counts. A crate that depends on the target both
+    // normally and as optional/dev is counted under `def` only, so `total()` counts it once.
+    let reverse_deps: HashMap<db_dump::crates::CrateId, RevDepCount> = reverse_deps
+        .into_iter()
+        .map(|(crate_id, (def, opt))| {
+            (
+                crate_id,
+                RevDepCount {
+                    def: def.len() as u32,
+                    opt: opt.difference(&def).count() as u32,
+                },
+            )
+        })
+        .collect();
+
+    // Join crate_owners against users/teams, leaving the owner out rather than failing the whole
+    // load when its user or team row is missing from the dump. A user's github id is copied as
+    // the dump has it.
+    let mut owners_by_crate: HashMap<db_dump::crates::CrateId, Vec<CrateOwner>> = HashMap::new();
+    for row in crate_owner_rows {
+        let owner = if row.owner_kind == db_dump::crate_owners::OwnerKind::Team {
+            teams_by_id
+                .get(&db_dump::teams::TeamId(row.owner_id))
+                .map(|team| CrateOwner {
+                    login: team.login.clone(),
+                    display_name: team.name.clone(),
+                    github_id: None,
+                    kind: CrateOwnerKind::Team,
+                    avatar_url: team.avatar.clone(),
+                })
+        } else {
+            users_by_id
+                .get(&db_dump::users::UserId(row.owner_id))
+                .map(|user| CrateOwner {
+                    login: user.gh_login.clone(),
+                    display_name: user.name.clone(),
+                    github_id: user.gh_id,
+                    kind: CrateOwnerKind::User,
+                    avatar_url: user.gh_avatar.clone(),
+                })
+        };
+
+        if let Some(owner) = owner {
+            owners_by_crate
+                .entry(row.crate_id)
+                .or_default()
+                .push(owner);
+        }
+    }
+
+    if !reverse_deps.is_empty() || !owners_by_crate.is_empty() {
+        let crate_id_by_name: HashMap<&str, db_dump::crates::CrateId> =
+            crates.iter().map(|c| (c.name.as_str(), c.id)).collect();
+
+        for gen in generated.iter_mut() {
+            let name = match gen.krate.as_ref() {
+                Some(krate) => krate.name.as_str(),
+                None => continue,
+            };
+
+            let crate_id = crate_id_by_name.get(name);
+
+            gen.reverse_dependency_count = crate_id
+                .and_then(|crate_id| reverse_deps.get(crate_id))
+                .map(RevDepCount::total);
+
+            gen.owners = crate_id
+                .and_then(|crate_id| owners_by_crate.get(crate_id))
+                .cloned()
+                .unwrap_or_default();
+        }
+    }
+
+    // Downloads are scored relative to the busiest crate in the whole generated set, so scoring
+    // has to happen in a second pass once that corpus-wide maximum is known.
+    let max_recent_downloads = generated
+        .iter()
+        .filter_map(|gen| gen.krate.as_ref().and_then(|k| k.recent_downloads))
+        .max()
+        .unwrap_or(0);
+    let max_reverse_dependency_count = generated
+        .iter()
+        .filter_map(|gen| gen.reverse_dependency_count)
+        .max()
+        .unwrap_or(0);
+
+    for gen in generated.iter_mut() {
+        gen.update_score(max_recent_downloads, max_reverse_dependency_count);
+        gen.update_suggested_topics();
+    }
+
     println!("{}", serde_json::to_string(&generated).unwrap());
     write_yaml("_data/crates_generated.yaml", generated)
 }
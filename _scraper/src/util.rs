@@ -0,0 +1,79 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+static CACHE_DIR: &str = ".cache";
+
+/// Matches the db-dump's own refresh window (see `make_sure_db_is_available` in `main`), so a
+/// cached Github response and the db-dump it's paired with go stale on roughly the same cadence.
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub fn read_yaml<T: DeserializeOwned>(path: &str) -> Result<T> {
+    let file = fs::File::open(path).with_context(|| format!("opening {}", path))?;
+    serde_yaml::from_reader(file).with_context(|| format!("parsing {}", path))
+}
+
+pub fn write_yaml<T: Serialize>(path: &str, value: T) -> Result<()> {
+    let file = fs::File::create(path).with_context(|| format!("creating {}", path))?;
+    serde_yaml::to_writer(file, &value).with_context(|| format!("writing {}", path))
+}
+
+pub fn cache_path(namespace: &str, key: &str) -> Result<PathBuf> {
+    let dir = Path::new(CACHE_DIR).join(namespace);
+    fs::create_dir_all(&dir).with_context(|| format!("creating cache dir {}", dir.display()))?;
+    Ok(dir.join(format!("{}.msgpack", key)))
+}
+
+// Wraps cached data with a schema-version tag and a fetch timestamp, so stale or
+// no-longer-compatible entries can be rejected instead of returned (or failing to deserialize)
+// outright.
+#[derive(Serialize, Deserialize)]
+struct CacheEnvelope<T> {
+    schema_version: u32,
+    fetched_at: DateTime<Utc>,
+    data: T,
+}
+
+/// Reads a cache entry written by `write_cache`. Fails - forcing the caller to refetch - if the
+/// entry is missing, was written under a different `schema_version`, or is older than `ttl`.
+pub fn read_cache<T: DeserializeOwned>(path: &Path, schema_version: u32, ttl: Duration) -> Result<T> {
+    let bytes = fs::read(path).with_context(|| format!("reading cache file {}", path.display()))?;
+    let envelope: CacheEnvelope<T> = rmp_serde::from_slice(&bytes)
+        .with_context(|| format!("decoding cache file {}", path.display()))?;
+
+    if envelope.schema_version != schema_version {
+        bail!(
+            "cache schema mismatch for {}: expected {}, found {}",
+            path.display(),
+            schema_version,
+            envelope.schema_version
+        );
+    }
+
+    let age = Utc::now().signed_duration_since(envelope.fetched_at);
+    if age.num_seconds() < 0 || age.num_seconds() as u64 > ttl.as_secs() {
+        bail!("cache entry for {} is older than the {:?} TTL", path.display(), ttl);
+    }
+
+    Ok(envelope.data)
+}
+
+/// Serializes `data` as MessagePack, tagged with `schema_version` and the current time, and
+/// writes it to `path`.
+pub fn write_cache<T: Serialize>(path: &Path, schema_version: u32, data: &T) -> Result<()> {
+    let envelope = CacheEnvelope {
+        schema_version,
+        fetched_at: Utc::now(),
+        data,
+    };
+    let bytes = rmp_serde::to_vec(&envelope)
+        .with_context(|| format!("encoding cache file {}", path.display()))?;
+
+    fs::write(path, bytes).with_context(|| format!("writing cache file {}", path.display()))
+}